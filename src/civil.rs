@@ -0,0 +1,246 @@
+//! Pure-Rust civil-date backend (enabled by the `pure-rust` feature).
+//!
+//! Replaces `gmtime_r`/`mktime`/`strftime`/`strptime` with Howard Hinnant's
+//! branch-free civil-date conversions plus a small strftime-directive
+//! interpreter, so the crate works on targets without a usable libc (wasm,
+//! SGX, embedded), mirroring how chrono and prost-types avoid the C library.
+//! `Tz::Local` has no meaning without an OS timezone database, so it is
+//! treated the same as `Tz::Utc` here.
+//!
+//! Only `%Y %m %d %H %M %S %j %a %A %b %B %p %%` are supported; `%j %a %A
+//! %b %B %p` are format-only (parsing them back is not implemented).
+
+use crate::civil_math::{days_from_civil, epoch_to_civil};
+use crate::{find_frac_token, Error, Tz};
+
+/// 0 = Sunday .. 6 = Saturday, for the given days-since-epoch. 1970-01-01
+/// (day 0) was a Thursday.
+fn weekday_from_days(z: i64) -> u32 {
+    (if z >= -4 { (z + 4) % 7 } else { (z + 5) % 7 + 6 }) as u32
+}
+
+fn day_of_year(y: i64, m: u32, d: u32) -> u32 {
+    (days_from_civil(y, m, d) - days_from_civil(y, 1, 1) + 1) as u32
+}
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday",
+];
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+fn tz_offset_secs(tz: Tz) -> i64 {
+    match tz {
+        Tz::FixedOffset(secs) => secs as i64,
+        Tz::Utc | Tz::Local => 0,
+    }
+}
+
+/// Renders `secs` (interpreted per `tz`) using the supported directive
+/// subset.
+fn render(secs: i64, tz: Tz, format: &str) -> Result<String, Error> {
+    let local_secs = secs + tz_offset_secs(tz);
+    let (y, m, d, h, mi, s) = epoch_to_civil(local_secs);
+    let wday = weekday_from_days(local_secs.div_euclid(86400)) as usize;
+    let yday = day_of_year(y, m, d);
+
+    let mut out = String::with_capacity(format.len());
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => out.push_str(&y.to_string()),
+            Some('m') => out.push_str(&format!("{:02}", m)),
+            Some('d') => out.push_str(&format!("{:02}", d)),
+            Some('H') => out.push_str(&format!("{:02}", h)),
+            Some('M') => out.push_str(&format!("{:02}", mi)),
+            Some('S') => out.push_str(&format!("{:02}", s)),
+            Some('j') => out.push_str(&format!("{:03}", yday)),
+            Some('a') => out.push_str(&WEEKDAYS[wday][..3]),
+            Some('A') => out.push_str(WEEKDAYS[wday]),
+            Some('b') => out.push_str(&MONTHS[(m - 1) as usize][..3]),
+            Some('B') => out.push_str(MONTHS[(m - 1) as usize]),
+            Some('p') => out.push_str(if h < 12 { "AM" } else { "PM" }),
+            Some('%') => out.push('%'),
+            _ => return Err(Error::FormatError),
+        }
+    }
+    Ok(out)
+}
+
+/// The civil fields accumulated while matching a format against input
+/// bytes. Mirrors the `tm` fields the libc backend fills via `strptime`.
+#[derive(Debug, Clone, Copy)]
+struct Fields {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+}
+
+impl Default for Fields {
+    fn default() -> Self {
+        // Mirrors the libc backend's zeroed `tm` (`tm_year = 0` is 1900,
+        // `tm_mon = 0` is January, `tm_mday = 0` is "the day before the
+        // 1st"), so a partial format like `"%Y-%m"` normalizes the missing
+        // day the same way under both backends.
+        Self { year: 1900, month: 1, day: 0, hour: 0, minute: 0, second: 0 }
+    }
+}
+
+impl Fields {
+    fn to_epoch_secs(self) -> i64 {
+        crate::civil_math::civil_to_epoch(self.year, self.month, self.day, self.hour, self.minute, self.second)
+    }
+}
+
+/// Consumes up to `max_digits` ASCII digits starting at `pos`, returning the
+/// parsed value and the position just past them.
+fn take_number(bytes: &[u8], pos: usize, max_digits: usize) -> Result<(u32, usize), Error> {
+    let mut end = pos;
+    while end < bytes.len() && end - pos < max_digits && bytes[end].is_ascii_digit() {
+        end += 1;
+    }
+    if end == pos {
+        return Err(Error::DateTimeParseError);
+    }
+    let value: u32 = std::str::from_utf8(&bytes[pos..end])
+        .unwrap()
+        .parse()
+        .map_err(|_| Error::DateTimeParseError)?;
+    Ok((value, end))
+}
+
+/// Matches `format` against `bytes` starting at `pos`, updating `fields` in
+/// place, and returns the position just past the match. Like `strptime`,
+/// this can be called repeatedly on the same `fields` to parse a string in
+/// pieces (used by `parse_strftime_nanos` to skip over a fractional-second token).
+fn apply_format(bytes: &[u8], pos: usize, format: &str, fields: &mut Fields) -> Result<usize, Error> {
+    let mut pos = pos;
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            if pos >= bytes.len() || bytes[pos] != c as u8 {
+                return Err(Error::DateTimeParseError);
+            }
+            pos += 1;
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => { let (v, next) = take_number(bytes, pos, 4)?; fields.year = v as i64; pos = next; }
+            Some('m') => { let (v, next) = take_number(bytes, pos, 2)?; fields.month = v; pos = next; }
+            Some('d') => { let (v, next) = take_number(bytes, pos, 2)?; fields.day = v; pos = next; }
+            Some('H') => { let (v, next) = take_number(bytes, pos, 2)?; fields.hour = v; pos = next; }
+            Some('M') => { let (v, next) = take_number(bytes, pos, 2)?; fields.minute = v; pos = next; }
+            Some('S') => { let (v, next) = take_number(bytes, pos, 2)?; fields.second = v; pos = next; }
+            Some('%') => {
+                if bytes.get(pos) != Some(&b'%') {
+                    return Err(Error::DateTimeParseError);
+                }
+                pos += 1;
+            }
+            _ => return Err(Error::DateTimeParseError),
+        }
+    }
+    Ok(pos)
+}
+
+/// Parses `date_time` against `format`, interpreting the result as being in
+/// `tz`.
+fn parse(date_time: &str, tz: Tz, format: &str) -> Result<i64, Error> {
+    let bytes = date_time.as_bytes();
+    let mut fields = Fields::default();
+    let end = apply_format(bytes, 0, format, &mut fields)?;
+    if end != bytes.len() {
+        return Err(Error::DateTimeParseError);
+    }
+    Ok(fields.to_epoch_secs() - tz_offset_secs(tz))
+}
+
+/// Formats a timestamp in seconds to date time in the specified format,
+/// interpreting `timestamp` in UTC.
+pub fn strftime_format(timestamp: i64, format: impl AsRef<str>) -> Result<String, Error> {
+    strftime_format_tz(timestamp, Tz::Utc, format)
+}
+
+/// Formats a timestamp in seconds to date time in the specified format and
+/// timezone.
+pub fn strftime_format_tz(timestamp: i64, tz: Tz, format: impl AsRef<str>) -> Result<String, Error> {
+    render(timestamp, tz, format.as_ref())
+}
+
+/// Parses a string date time into timestamp in seconds. Since there is no
+/// OS-provided local timezone without libc, this is equivalent to
+/// `parse_strftime_tz(date_time, Tz::Utc, format)`.
+pub fn parse_strftime(date_time: impl AsRef<str>, format: impl AsRef<str>) -> Result<i64, Error> {
+    parse_strftime_tz(date_time, Tz::Utc, format)
+}
+
+/// Parses a string date time into timestamp in seconds using the specified
+/// format, interpreting the parsed fields as being in `tz`.
+pub fn parse_strftime_tz(date_time: impl AsRef<str>, tz: Tz, format: impl AsRef<str>) -> Result<i64, Error> {
+    parse(date_time.as_ref(), tz, format.as_ref())
+}
+
+/// Parses a string date time with a fractional-second component into a
+/// `(timestamp_secs, nanos)` pair, interpreted in UTC.
+pub fn parse_strftime_nanos(date_time: impl AsRef<str>, format: impl AsRef<str>) -> Result<(i64, u32), Error> {
+    let date_time = date_time.as_ref();
+    let format = format.as_ref();
+    let bytes = date_time.as_bytes();
+
+    let (start, end, precision) = match find_frac_token(format) {
+        None => return Ok((parse_strftime_tz(date_time, Tz::Utc, format)?, 0)),
+        Some(token) => token,
+    };
+    let before = &format[..start];
+    let after = &format[end..];
+    let precision = precision as usize;
+
+    let mut fields = Fields::default();
+    let pos = apply_format(bytes, 0, before, &mut fields)?;
+    if pos + precision > bytes.len() {
+        return Err(Error::DateTimeParseError);
+    }
+    let frac_bytes = &bytes[pos..pos + precision];
+    if !frac_bytes.iter().all(u8::is_ascii_digit) {
+        return Err(Error::DateTimeParseError);
+    }
+    let frac_value: u32 = std::str::from_utf8(frac_bytes)
+        .unwrap()
+        .parse()
+        .map_err(|_| Error::DateTimeParseError)?;
+    let nanos = frac_value * 10u32.pow(9 - precision as u32);
+
+    let pos = apply_format(bytes, pos + precision, after, &mut fields)?;
+    if pos != bytes.len() {
+        return Err(Error::DateTimeParseError);
+    }
+
+    Ok((fields.to_epoch_secs() - tz_offset_secs(Tz::Utc), nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_to_civil() {
+        assert_eq!(epoch_to_civil(0), (1970, 1, 1, 0, 0, 0));
+        assert_eq!(epoch_to_civil(1669113150), (2022, 11, 22, 10, 32, 30));
+    }
+
+    #[test]
+    fn test_partial_format_matches_libc_backend_day_normalization() {
+        // A missing day defaults to tm_mday = 0 ("the day before the 1st"),
+        // matching the libc backend's zeroed tm, not day 1.
+        assert_eq!(parse_strftime("2022-11", "%Y-%m").unwrap(), 1667174400); // 2022-10-31
+    }
+}
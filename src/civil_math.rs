@@ -0,0 +1,61 @@
+//! Howard Hinnant's branch-free proleptic-Gregorian civil-date conversions.
+//!
+//! Shared by the pure-Rust [`crate::civil`] backend, the libc backend's
+//! fixed-width fast path, [`crate::packed`] and [`crate::rfc`], so all of
+//! them can turn Y/M/D fields into days-since-epoch without going through
+//! `tm`/`timegm`.
+
+/// Days since the Unix epoch (1970-01-01) for a proleptic-Gregorian civil
+/// date.
+pub(crate) fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = y - (m <= 2) as i64;
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400; // 0..=399
+    let m = m as i64;
+    let d = d as i64;
+    // Signed so that d = 0 ("the day before the 1st") gives doy = -1 for
+    // March instead of underflowing.
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1; // 0..=365 for d >= 1
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Inverse of [`days_from_civil`], returning `(year, month, day)`.
+pub(crate) fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = (z - era * 146097) as u64; // 0..=146096
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // 0..=399
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // 0..=365
+    let mp = (5 * doy + 2) / 153; // 0..=11
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32; // 1..=31
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32; // 1..=12
+    (y + (m <= 2) as i64, m, d)
+}
+
+/// Splits epoch seconds into civil `(year, month, day, hour, min, sec)`.
+pub(crate) fn epoch_to_civil(secs: i64) -> (i64, u32, u32, u32, u32, u32) {
+    let days = secs.div_euclid(86400);
+    let rem = secs.rem_euclid(86400);
+    let (y, m, d) = civil_from_days(days);
+    (y, m, d, (rem / 3600) as u32, (rem / 60 % 60) as u32, (rem % 60) as u32)
+}
+
+/// Combines civil `(year, month, day, hour, min, sec)` into epoch seconds.
+pub(crate) fn civil_to_epoch(y: i64, m: u32, d: u32, h: u32, mi: u32, s: u32) -> i64 {
+    days_from_civil(y, m, d) * 86400 + h as i64 * 3600 + mi as i64 * 60 + s as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for days in [-719468, -1, 0, 1, 19723, 1_000_000] {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+    }
+}
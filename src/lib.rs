@@ -4,57 +4,47 @@
 //! - It parses string date to Unix timestamp.
 //! - It formats Unix timestamp into string date.
 //!
+//! Two interchangeable backends implement the actual conversions:
+//! - the default, libc-backed backend (`gmtime_r`/`mktime`/`strftime`/`strptime`)
+//! - a pure-Rust backend behind the `pure-rust` feature, for targets without
+//!   a usable libc
 
 // TODO:  docs & doc test
 
-use std::{
-    ffi::CString,
-    fmt,
-    os::raw::{c_char, c_int, c_long},
-};
-
-#[allow(non_camel_case_types)]
-type c_time_t = i64;
-
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-struct tm {
-    pub tm_sec: c_int,
-    pub tm_min: c_int,
-    pub tm_hour: c_int,
-    pub tm_mday: c_int,
-    pub tm_mon: c_int,
-    pub tm_year: c_int,
-    pub tm_wday: c_int,
-    pub tm_yday: c_int,
-    pub tm_isdst: c_int,
-    pub tm_gmtoff: c_long,
-    pub tm_zone: *mut c_char,
-}
+use std::fmt;
 
-impl Default for tm {
-    fn default() -> Self {
-        Self { 
-            tm_sec: 0,
-            tm_min: 0,
-            tm_hour: 0,
-            tm_mday: 0,
-            tm_mon: 0,
-            tm_year: 0,
-            tm_wday: 0,
-            tm_yday: 0,
-            tm_isdst: 0,
-            tm_gmtoff: 0,
-            tm_zone: std::ptr::null_mut(),
-         }
-    }
-}
+mod civil_math;
+
+#[cfg(not(feature = "pure-rust"))]
+mod libc_backend;
+#[cfg(not(feature = "pure-rust"))]
+pub use libc_backend::{parse_strftime, parse_strftime_nanos, parse_strftime_tz, strftime_format, strftime_format_tz};
 
-extern "C" {
-    fn gmtime_r(timestamp: *const c_time_t, tm: *mut tm) -> *mut tm;
-    fn strftime(s: *mut c_char, maxsize: usize, format: *const c_char, timeptr: *const tm) -> usize;
-    fn strptime(s: *const c_char, format: *const c_char, timeptr: *const tm) -> *mut c_char;
-    fn mktime(timeptr: *mut tm) -> i64;
+#[cfg(feature = "pure-rust")]
+mod civil;
+#[cfg(feature = "pure-rust")]
+pub use civil::{parse_strftime, parse_strftime_nanos, parse_strftime_tz, strftime_format, strftime_format_tz};
+
+mod packed;
+pub use packed::PackedTimestamp;
+
+mod rfc;
+pub use rfc::{format_rfc2822, format_rfc3339, parse_rfc2822, parse_rfc3339};
+
+/// The timezone a timestamp should be interpreted in (when parsing) or
+/// rendered in (when formatting).
+///
+/// `Utc` and `FixedOffset` round-trip exactly: formatting a timestamp and
+/// parsing the result back with the same `Tz` always yields the original
+/// timestamp. `Local` depends on the process's local timezone (`TZ`), like
+/// the C library it wraps, and is unavailable (treated as `Utc`) under the
+/// `pure-rust` feature, which has no OS timezone database to consult.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Tz {
+    Utc,
+    Local,
+    /// Offset from UTC in seconds, e.g. `3600` for `+01:00`.
+    FixedOffset(i32),
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -78,70 +68,63 @@ impl fmt::Display for Error {
 
 impl std::error::Error for Error {}
 
-/// Formats a timestamp in seconds to date time in the specified format.
-pub fn strftime_format(timestamp: i64, format: impl AsRef<str>) -> Result<String, Error> {
-    let format = format.as_ref();
-    let mut tm = tm::default();
-    if unsafe { gmtime_r(&timestamp, &mut tm as *mut tm) }.is_null() {
-        return Err(Error::TimestampToTmError);
-    }
+/// Locates a fractional-second token (`%f`, `%3f`, `%6f`, `%9f`) in `format`,
+/// returning its byte range and digit precision. `%f` is treated as
+/// nanosecond (9-digit) precision. Only the leftmost token is reported;
+/// formats are not expected to contain more than one.
+fn find_frac_token(format: &str) -> Option<(usize, usize, u8)> {
+    const TOKENS: [(&str, u8); 4] = [("%9f", 9), ("%6f", 6), ("%3f", 3), ("%f", 9)];
+    TOKENS
+        .iter()
+        .filter_map(|(tok, precision)| format.find(tok).map(|idx| (idx, idx + tok.len(), *precision)))
+        .min_by_key(|(idx, _, _)| *idx)
+}
 
-    let format_len = format.len();
-    let format = CString::new(format).map_err(|_| Error::FormatError)?;
-    let mut buf_size = format_len;
-    let mut buf: Vec<u8> = vec![0; buf_size];
-    loop {
-        let len = unsafe {
-            strftime(
-                buf.as_mut_ptr() as *mut c_char,
-                buf_size,
-                format.as_ptr() as *const c_char,
-                &tm,
-            )
-        };
-        if len == 0 {
-            buf_size *= 2;
-            buf.resize(buf_size, 0);
-        } else {
-            buf.truncate(len);
-            return String::from_utf8(buf).map_err(|_| Error::FormatError);
-        }
+/// Renders `nanos` truncated to `precision` digits, zero-padded.
+fn format_fraction(nanos: u32, precision: u8) -> String {
+    let divisor = 10u32.pow(9 - precision as u32);
+    let value = nanos / divisor;
+    format!("{:0width$}", value, width = precision as usize)
+}
+
+/// Like `strftime_format`, but treats an empty format as an empty string
+/// rather than invoking the backend (whose "zero-length output" and
+/// "buffer too small" cases are otherwise ambiguous).
+fn strftime_format_segment(timestamp: i64, segment: &str) -> Result<String, Error> {
+    if segment.is_empty() {
+        Ok(String::new())
+    } else {
+        strftime_format(timestamp, segment)
     }
 }
 
-/// Parses a string date time into timestamp in seconds using the specified format.
-pub fn parse_strftime(date_time: impl AsRef<str>, format: impl AsRef<str>) -> Result<i64, Error> {
+/// Formats a timestamp in seconds and a nanoseconds component into date time
+/// in the specified format, interpreted in UTC.
+///
+/// `format` may contain one fractional-second token (`%f` for nanoseconds,
+/// or `%3f`/`%6f`/`%9f` for milli-/micro-/nanosecond precision) which is
+/// rendered from `nanos` directly in Rust, since neither backend has a
+/// fractional-second directive of its own.
+pub fn strftime_format_nanos(timestamp_secs: i64, nanos: u32, format: impl AsRef<str>) -> Result<String, Error> {
     let format = format.as_ref();
-    let format = CString::new(format).map_err(|_| Error::FormatError)?;
-    let date_time = date_time.as_ref();
-    let date_time = CString::new(date_time).map_err(|_| Error::FormatError)?;
-
-    let mut tm = tm::default();
-    if unsafe {
-        strptime(
-            date_time.as_ptr() as *const c_char,
-            format.as_ptr() as *const c_char,
-            &mut tm as *mut tm,
-        )
-    }.is_null() {
-        return Err(Error::DateTimeParseError);
-    }
-    // Use original value for error checking.
-    // mktime does not make use of fields (tm_wday, tm_yday) to calculate time_t,
-    // but if it succeeds, the value changes.
-    tm.tm_yday = -1; 
-    let timestamp = unsafe { mktime(&mut tm as *mut tm) };
-    if timestamp == -1 && tm.tm_yday == -1 {
-        return Err(Error::TimestampOverflowError);
+    match find_frac_token(format) {
+        None => strftime_format(timestamp_secs, format),
+        Some((start, end, precision)) => {
+            let prefix = strftime_format_segment(timestamp_secs, &format[..start])?;
+            let suffix = strftime_format_segment(timestamp_secs, &format[end..])?;
+            let frac = format_fraction(nanos, precision);
+            Ok(format!("{prefix}{frac}{suffix}"))
+        }
     }
-    
-    return Ok(timestamp)
 }
 
 #[cfg(test)]
 mod tests {
     use chrono::NaiveDateTime;
-    use crate::{parse_strftime, strftime_format};
+    use crate::{
+        parse_strftime, parse_strftime_nanos, parse_strftime_tz, strftime_format,
+        strftime_format_nanos, strftime_format_tz, Tz,
+    };
 
     #[test]
     fn test_parse_strftime() {
@@ -151,11 +134,11 @@ mod tests {
 
         let timestamp = parse_strftime("1969-12-31 23:59:59", "%Y-%m-%d %H:%M:%S").unwrap();
         let expected_timestamp = NaiveDateTime::parse_from_str("1969-12-31 23:59:59", "%Y-%m-%d %H:%M:%S").unwrap().timestamp();
-        assert_eq!(timestamp, expected_timestamp); 
+        assert_eq!(timestamp, expected_timestamp);
 
         let timestamp = parse_strftime("2022-11-22 10:12:30", "%Y-%m-%d %H:%M:%S").unwrap();
         let expected_timestamp = NaiveDateTime::parse_from_str("2022-11-22 10:12:30", "%Y-%m-%d %H:%M:%S").unwrap().timestamp();
-        assert_eq!(timestamp, expected_timestamp); 
+        assert_eq!(timestamp, expected_timestamp);
     }
 
     #[test]
@@ -164,4 +147,46 @@ mod tests {
         let date_time = strftime_format(timestamp, "%Y-%m-%d %H:%M:%S").unwrap();
         assert_eq!(date_time, "1969-12-31 23:59:59");
     }
+
+    #[test]
+    fn test_utc_round_trip_is_exact() {
+        // Unlike parse_strftime (which goes through mktime and depends on
+        // the process's local timezone), Tz::Utc must round-trip exactly.
+        for timestamp in [0_i64, 1669113150, -1] {
+            let date_time = strftime_format_tz(timestamp, Tz::Utc, "%Y-%m-%d %H:%M:%S").unwrap();
+            let round_tripped = parse_strftime_tz(&date_time, Tz::Utc, "%Y-%m-%d %H:%M:%S").unwrap();
+            assert_eq!(round_tripped, timestamp);
+        }
+    }
+
+    #[test]
+    fn test_fixed_offset_round_trip() {
+        let offset_secs = 3600; // +01:00
+        let timestamp = 0_i64;
+        let date_time = strftime_format_tz(timestamp, Tz::FixedOffset(offset_secs), "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(date_time, "1970-01-01 01:00:00");
+
+        let round_tripped = parse_strftime_tz(&date_time, Tz::FixedOffset(offset_secs), "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(round_tripped, timestamp);
+    }
+
+    #[test]
+    fn test_strftime_format_nanos() {
+        let date_time = strftime_format_nanos(0, 123_456_789, "%Y-%m-%dT%H:%M:%S.%3fZ").unwrap();
+        assert_eq!(date_time, "1970-01-01T00:00:00.123Z");
+
+        let date_time = strftime_format_nanos(0, 123_456_789, "%Y-%m-%dT%H:%M:%S.%9fZ").unwrap();
+        assert_eq!(date_time, "1970-01-01T00:00:00.123456789Z");
+    }
+
+    #[test]
+    fn test_parse_strftime_nanos_round_trip() {
+        let (timestamp, nanos) =
+            parse_strftime_nanos("1970-01-01T00:00:00.123456Z", "%Y-%m-%dT%H:%M:%S.%6fZ").unwrap();
+        assert_eq!(timestamp, 0);
+        assert_eq!(nanos, 123_456_000);
+
+        let date_time = strftime_format_nanos(timestamp, nanos, "%Y-%m-%dT%H:%M:%S.%6fZ").unwrap();
+        assert_eq!(date_time, "1970-01-01T00:00:00.123456Z");
+    }
 }
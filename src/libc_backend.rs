@@ -0,0 +1,417 @@
+//! libc-backed implementation (default; disabled by the `pure-rust` feature).
+//!
+//! Wraps `gmtime_r`/`localtime_r`/`mktime`/`timegm`/`strftime`/`strptime`
+//! from the platform's C library.
+
+use std::{
+    ffi::CString,
+    os::raw::{c_char, c_int, c_long},
+};
+
+use crate::civil_math::days_from_civil;
+use crate::{find_frac_token, Error, Tz};
+
+#[allow(non_camel_case_types)]
+type c_time_t = i64;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+struct tm {
+    pub tm_sec: c_int,
+    pub tm_min: c_int,
+    pub tm_hour: c_int,
+    pub tm_mday: c_int,
+    pub tm_mon: c_int,
+    pub tm_year: c_int,
+    pub tm_wday: c_int,
+    pub tm_yday: c_int,
+    pub tm_isdst: c_int,
+    pub tm_gmtoff: c_long,
+    pub tm_zone: *mut c_char,
+}
+
+impl Default for tm {
+    fn default() -> Self {
+        Self {
+            tm_sec: 0,
+            tm_min: 0,
+            tm_hour: 0,
+            tm_mday: 0,
+            tm_mon: 0,
+            tm_year: 0,
+            tm_wday: 0,
+            tm_yday: 0,
+            tm_isdst: 0,
+            tm_gmtoff: 0,
+            tm_zone: std::ptr::null_mut(),
+        }
+    }
+}
+
+extern "C" {
+    fn gmtime_r(timestamp: *const c_time_t, tm: *mut tm) -> *mut tm;
+    fn localtime_r(timestamp: *const c_time_t, tm: *mut tm) -> *mut tm;
+    fn strftime(s: *mut c_char, maxsize: usize, format: *const c_char, timeptr: *const tm) -> usize;
+    fn strptime(s: *const c_char, format: *const c_char, timeptr: *const tm) -> *mut c_char;
+    fn mktime(timeptr: *mut tm) -> i64;
+    fn timegm(timeptr: *mut tm) -> i64;
+}
+
+/// Builds the C `tm` for `timestamp` as seen in `tz`, along with the zone
+/// name string backing `tm_zone` (kept alive by the caller for the
+/// lifetime of the `tm`).
+fn tm_from_timestamp(timestamp: i64, tz: Tz) -> Result<(tm, CString), Error> {
+    let mut tm = tm::default();
+    match tz {
+        Tz::Local => {
+            if unsafe { localtime_r(&timestamp, &mut tm as *mut tm) }.is_null() {
+                return Err(Error::TimestampToTmError);
+            }
+            // tm_zone already points at a libc-owned static buffer; keep an
+            // empty CString around just to give the caller something to hold.
+            Ok((tm, CString::new("").unwrap()))
+        }
+        Tz::Utc => {
+            if unsafe { gmtime_r(&timestamp, &mut tm as *mut tm) }.is_null() {
+                return Err(Error::TimestampToTmError);
+            }
+            let zone = CString::new("UTC").unwrap();
+            tm.tm_gmtoff = 0;
+            tm.tm_zone = zone.as_ptr() as *mut c_char;
+            Ok((tm, zone))
+        }
+        Tz::FixedOffset(offset_secs) => {
+            let shifted = timestamp
+                .checked_add(offset_secs as i64)
+                .ok_or(Error::TimestampOverflowError)?;
+            if unsafe { gmtime_r(&shifted, &mut tm as *mut tm) }.is_null() {
+                return Err(Error::TimestampToTmError);
+            }
+            let zone = CString::new(format_offset(offset_secs)).unwrap();
+            tm.tm_gmtoff = offset_secs as c_long;
+            tm.tm_zone = zone.as_ptr() as *mut c_char;
+            Ok((tm, zone))
+        }
+    }
+}
+
+/// Formats an offset in seconds as a `+HHMM`/`-HHMM` string, matching `%z`.
+fn format_offset(offset_secs: i32) -> String {
+    let sign = if offset_secs < 0 { '-' } else { '+' };
+    // `abs()` panics on `i32::MIN` (no positive counterpart); `unsigned_abs()`
+    // handles the full range, same as `packed.rs`'s offset handling.
+    let total_minutes = offset_secs.unsigned_abs() / 60;
+    format!("{}{:02}{:02}", sign, total_minutes / 60, total_minutes % 60)
+}
+
+fn tz_offset_secs(tz: Tz) -> i64 {
+    match tz {
+        Tz::FixedOffset(secs) => secs as i64,
+        Tz::Utc | Tz::Local => 0,
+    }
+}
+
+/// Byte offsets of the 14 ASCII-digit positions in a `YYYY-MM-DD?HH:MM:SS`
+/// layout (the `?` separator is checked separately).
+const DIGIT_POSITIONS: [usize; 14] = [0, 1, 2, 3, 5, 6, 8, 9, 11, 12, 14, 15, 17, 18];
+
+/// Parses `date_time` as UTC seconds without going through libc, if `format`
+/// is one of the recognized fixed-width RFC-3339-ish layouts. Returns
+/// `None` (never an error) for anything else, so the caller can fall back
+/// to the general `strptime` path unchanged.
+fn fast_parse_fixed_width(date_time: &str, format: &str) -> Option<i64> {
+    let (sep, suffix): (u8, &[u8]) = match format {
+        "%Y-%m-%d %H:%M:%S" => (b' ', b""),
+        "%Y-%m-%dT%H:%M:%SZ" => (b'T', b"Z"),
+        _ => return None,
+    };
+    let bytes = date_time.as_bytes();
+    let expected_len = 19 + suffix.len();
+    if bytes.len() != expected_len || &bytes[19..] != suffix {
+        return None;
+    }
+    if bytes[4] != b'-' || bytes[7] != b'-' || bytes[10] != sep || bytes[13] != b':' || bytes[16] != b':' {
+        return None;
+    }
+    let mut digits = [0u8; 19];
+    digits.copy_from_slice(&bytes[0..19]);
+    if !digits_are_ascii(&digits) {
+        return None;
+    }
+
+    let fold2 = |a: u8, b: u8| (a - b'0') as u32 * 10 + (b - b'0') as u32;
+    let fold4 = |a: u8, b: u8, c: u8, d: u8| {
+        (a - b'0') as u32 * 1000 + (b - b'0') as u32 * 100 + (c - b'0') as u32 * 10 + (d - b'0') as u32
+    };
+    let year = fold4(digits[0], digits[1], digits[2], digits[3]) as i64;
+    let month = fold2(digits[5], digits[6]);
+    let day = fold2(digits[8], digits[9]);
+    let hour = fold2(digits[11], digits[12]);
+    let minute = fold2(digits[14], digits[15]);
+    let second = fold2(digits[17], digits[18]);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    Some(days_from_civil(year, month, day) * 86400 + hour as i64 * 3600 + minute as i64 * 60 + second as i64)
+}
+
+/// Validates that the 14 digit positions of a `YYYY-MM-DD?HH:MM:SS` buffer
+/// (separators already checked) are all ASCII `0..=9`, in one masked SSE2
+/// compare on `x86_64` (falling back to a scalar loop elsewhere, or if the
+/// CPU lacks SSE2 — which in practice is never, but `strptime` is always
+/// there as a correctness backstop regardless).
+fn digits_are_ascii(bytes: &[u8; 19]) -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("sse2") {
+            return unsafe { digits_are_ascii_sse2(bytes) };
+        }
+    }
+    DIGIT_POSITIONS.iter().all(|&i| bytes[i].is_ascii_digit())
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn digits_are_ascii_sse2(bytes: &[u8; 19]) -> bool {
+    use std::arch::x86_64::{
+        _mm_and_si128, _mm_cmpgt_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_set1_epi8, _mm_sub_epi8,
+    };
+
+    // Overwrite the non-digit separator positions with a known digit so the
+    // single 16-lane vector compare below can cover bytes 0..16 in one shot;
+    // the trailing seconds digits at 17..19 fall outside that window and are
+    // checked separately.
+    let mut masked = *bytes;
+    for &i in &[4usize, 7, 10, 13, 16] {
+        masked[i] = b'0';
+    }
+
+    let chunk = _mm_loadu_si128(masked[0..16].as_ptr() as *const _);
+    let shifted = _mm_sub_epi8(chunk, _mm_set1_epi8(b'0' as i8));
+    // shifted in 0..=9 <=> (shifted > -1) && (shifted < 10)
+    let above_min = _mm_cmpgt_epi8(shifted, _mm_set1_epi8(-1));
+    let below_max = _mm_cmpgt_epi8(_mm_set1_epi8(10), shifted);
+    let in_range = _mm_and_si128(above_min, below_max);
+    if _mm_movemask_epi8(in_range) != 0xFFFF {
+        return false;
+    }
+
+    bytes[17].is_ascii_digit() && bytes[18].is_ascii_digit()
+}
+
+/// Formats a timestamp in seconds to date time in the specified format,
+/// interpreting `timestamp` in UTC.
+///
+/// Equivalent to `strftime_format_tz(timestamp, Tz::Utc, format)`.
+pub fn strftime_format(timestamp: i64, format: impl AsRef<str>) -> Result<String, Error> {
+    strftime_format_tz(timestamp, Tz::Utc, format)
+}
+
+/// Formats a timestamp in seconds to date time in the specified format and
+/// timezone.
+pub fn strftime_format_tz(timestamp: i64, tz: Tz, format: impl AsRef<str>) -> Result<String, Error> {
+    let format = format.as_ref();
+    let (tm, _zone) = tm_from_timestamp(timestamp, tz)?;
+
+    let format_len = format.len();
+    let format = CString::new(format).map_err(|_| Error::FormatError)?;
+    let mut buf_size = format_len;
+    let mut buf: Vec<u8> = vec![0; buf_size];
+    loop {
+        let len = unsafe {
+            strftime(
+                buf.as_mut_ptr() as *mut c_char,
+                buf_size,
+                format.as_ptr() as *const c_char,
+                &tm,
+            )
+        };
+        if len == 0 {
+            buf_size *= 2;
+            buf.resize(buf_size, 0);
+        } else {
+            buf.truncate(len);
+            return String::from_utf8(buf).map_err(|_| Error::FormatError);
+        }
+    }
+}
+
+/// Parses a string date time into timestamp in seconds using the specified
+/// format, interpreting the parsed fields in the process's local timezone.
+///
+/// Equivalent to `parse_strftime_tz(date_time, Tz::Local, format)`.
+pub fn parse_strftime(date_time: impl AsRef<str>, format: impl AsRef<str>) -> Result<i64, Error> {
+    parse_strftime_tz(date_time, Tz::Local, format)
+}
+
+/// Parses a string date time into timestamp in seconds using the specified
+/// format, interpreting the parsed fields as being in `tz`.
+///
+/// Unlike `parse_strftime`, `Tz::Utc` and `Tz::FixedOffset` do not depend on
+/// the process's local timezone, so they are exact inverses of
+/// `strftime_format_tz` called with the same `tz`.
+pub fn parse_strftime_tz(date_time: impl AsRef<str>, tz: Tz, format: impl AsRef<str>) -> Result<i64, Error> {
+    let date_time = date_time.as_ref();
+    let format = format.as_ref();
+
+    // Most callers use one of a handful of fixed-width layouts; parse those
+    // directly instead of paying for a CString allocation plus a strptime
+    // call. Only safe for Utc/FixedOffset, since the result is computed via
+    // civil-days rather than the process's local timezone.
+    if !matches!(tz, Tz::Local) {
+        if let Some(utc_timestamp) = fast_parse_fixed_width(date_time, format) {
+            return utc_timestamp
+                .checked_sub(tz_offset_secs(tz))
+                .ok_or(Error::TimestampOverflowError);
+        }
+    }
+
+    let format = CString::new(format).map_err(|_| Error::FormatError)?;
+    let date_time = CString::new(date_time).map_err(|_| Error::FormatError)?;
+
+    let mut tm = tm::default();
+    if unsafe {
+        strptime(
+            date_time.as_ptr() as *const c_char,
+            format.as_ptr() as *const c_char,
+            &mut tm as *mut tm,
+        )
+    }.is_null() {
+        return Err(Error::DateTimeParseError);
+    }
+    // Use original value for error checking.
+    // mktime/timegm do not make use of fields (tm_wday, tm_yday) to calculate
+    // time_t, but if they succeed, the value changes.
+    tm.tm_yday = -1;
+    let timestamp = match tz {
+        Tz::Local => unsafe { mktime(&mut tm as *mut tm) },
+        Tz::Utc => unsafe { timegm(&mut tm as *mut tm) },
+        Tz::FixedOffset(offset_secs) => {
+            let utc_timestamp = unsafe { timegm(&mut tm as *mut tm) };
+            if utc_timestamp == -1 && tm.tm_yday == -1 {
+                return Err(Error::TimestampOverflowError);
+            }
+            return utc_timestamp
+                .checked_sub(offset_secs as i64)
+                .ok_or(Error::TimestampOverflowError);
+        }
+    };
+    if timestamp == -1 && tm.tm_yday == -1 {
+        return Err(Error::TimestampOverflowError);
+    }
+
+    return Ok(timestamp)
+}
+
+/// Parses a string date time with a fractional-second component into a
+/// `(timestamp_secs, nanos)` pair using the specified format, interpreted in
+/// UTC.
+///
+/// `format` may contain one fractional-second token (`%f`, `%3f`, `%6f`,
+/// `%9f`); the matching digits in `date_time` are split out before handing
+/// the rest of the string to `strptime`, since it cannot parse them either.
+pub fn parse_strftime_nanos(date_time: impl AsRef<str>, format: impl AsRef<str>) -> Result<(i64, u32), Error> {
+    let date_time = date_time.as_ref();
+    let format = format.as_ref();
+
+    let (start, end, precision) = match find_frac_token(format) {
+        None => return Ok((parse_strftime_tz(date_time, Tz::Utc, format)?, 0)),
+        Some(token) => token,
+    };
+    let before = &format[..start];
+    let after = &format[end..];
+
+    let date_time_c = CString::new(date_time).map_err(|_| Error::FormatError)?;
+    let before_c = CString::new(before).map_err(|_| Error::FormatError)?;
+
+    let mut tm = tm::default();
+    let remainder = unsafe { strptime(date_time_c.as_ptr(), before_c.as_ptr(), &mut tm as *mut tm) };
+    if remainder.is_null() {
+        return Err(Error::DateTimeParseError);
+    }
+    let offset = remainder as usize - date_time_c.as_ptr() as usize;
+
+    let bytes = date_time.as_bytes();
+    let precision = precision as usize;
+    if offset + precision > bytes.len() {
+        return Err(Error::DateTimeParseError);
+    }
+    let frac_bytes = &bytes[offset..offset + precision];
+    if !frac_bytes.iter().all(u8::is_ascii_digit) {
+        return Err(Error::DateTimeParseError);
+    }
+    let frac_value: u32 = std::str::from_utf8(frac_bytes)
+        .unwrap()
+        .parse()
+        .map_err(|_| Error::DateTimeParseError)?;
+    let nanos = frac_value * 10u32.pow(9 - precision as u32);
+
+    let rest = &date_time[offset + precision..];
+    if after.is_empty() {
+        if !rest.is_empty() {
+            return Err(Error::DateTimeParseError);
+        }
+    } else {
+        let after_c = CString::new(after).map_err(|_| Error::FormatError)?;
+        let rest_c = CString::new(rest).map_err(|_| Error::FormatError)?;
+        if unsafe { strptime(rest_c.as_ptr(), after_c.as_ptr(), &mut tm as *mut tm) }.is_null() {
+            return Err(Error::DateTimeParseError);
+        }
+    }
+
+    tm.tm_yday = -1;
+    let timestamp = unsafe { timegm(&mut tm as *mut tm) };
+    if timestamp == -1 && tm.tm_yday == -1 {
+        return Err(Error::TimestampOverflowError);
+    }
+
+    Ok((timestamp, nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fast_parse_fixed_width() {
+        assert_eq!(
+            fast_parse_fixed_width("1970-01-01 00:00:00", "%Y-%m-%d %H:%M:%S"),
+            Some(0)
+        );
+        assert_eq!(
+            fast_parse_fixed_width("2022-11-22T10:32:30Z", "%Y-%m-%dT%H:%M:%SZ"),
+            Some(1669113150)
+        );
+        // Anything that doesn't fit exactly falls back (returns None)
+        // rather than erroring, so the caller can retry via strptime.
+        assert_eq!(fast_parse_fixed_width("2022-11-22 10:32:3a", "%Y-%m-%d %H:%M:%S"), None);
+        assert_eq!(fast_parse_fixed_width("2022-13-22 10:32:30", "%Y-%m-%d %H:%M:%S"), None);
+        assert_eq!(fast_parse_fixed_width("not-a-date-string!!!", "%Y-%m-%d %H:%M:%S"), None);
+    }
+
+    #[test]
+    fn test_format_offset_does_not_panic_on_extreme_values() {
+        assert_eq!(format_offset(i32::MIN), "-59652314");
+        assert_eq!(format_offset(i32::MAX), "+59652314");
+    }
+
+    #[test]
+    fn test_parse_strftime_tz_matches_slow_path() {
+        for (date_time, format) in [
+            ("1970-01-01 00:00:00", "%Y-%m-%d %H:%M:%S"),
+            ("2022-11-22 10:32:30", "%Y-%m-%d %H:%M:%S"),
+            ("2022-11-22T10:32:30Z", "%Y-%m-%dT%H:%M:%SZ"),
+        ] {
+            let fast = fast_parse_fixed_width(date_time, format).unwrap();
+            // Call strptime + timegm directly: parse_strftime_tz would itself
+            // take the fast path for these layouts.
+            let date_time_c = CString::new(date_time).unwrap();
+            let format_c = CString::new(format).unwrap();
+            let mut tm = tm::default();
+            assert!(!unsafe { strptime(date_time_c.as_ptr(), format_c.as_ptr(), &mut tm as *mut tm) }.is_null());
+            let slow = unsafe { timegm(&mut tm as *mut tm) };
+            assert_eq!(fast, slow);
+        }
+    }
+}
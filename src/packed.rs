@@ -0,0 +1,239 @@
+//! A bit-packed timestamp for compact (columnar) storage and constant-time
+//! truncation, following packedtime-rs's field-layout approach.
+
+use std::cmp::Ordering;
+
+use crate::civil_math::{civil_to_epoch, epoch_to_civil};
+use crate::{parse_strftime_tz, strftime_format_tz, Error, Tz};
+
+const OFFSET_BITS: u32 = 12;
+const MILLIS_BITS: u32 = 10;
+const SECOND_BITS: u32 = 6;
+const MINUTE_BITS: u32 = 6;
+const HOUR_BITS: u32 = 5;
+const DAY_BITS: u32 = 5;
+const MONTH_BITS: u32 = 4;
+const YEAR_BITS: u32 = 14;
+
+const MILLIS_SHIFT: u32 = 0;
+const SECOND_SHIFT: u32 = MILLIS_SHIFT + MILLIS_BITS;
+const MINUTE_SHIFT: u32 = SECOND_SHIFT + SECOND_BITS;
+const HOUR_SHIFT: u32 = MINUTE_SHIFT + MINUTE_BITS;
+const DAY_SHIFT: u32 = HOUR_SHIFT + HOUR_BITS;
+const MONTH_SHIFT: u32 = DAY_SHIFT + DAY_BITS;
+const YEAR_SHIFT: u32 = MONTH_SHIFT + MONTH_BITS;
+// Above year (not below any time field), so truncate_to_* never clears it.
+const OFFSET_SHIFT: u32 = YEAR_SHIFT + YEAR_BITS;
+
+/// Bias applied to `offset_min` so it packs as an unsigned field; covers
+/// the full +/-24h range a fixed offset can take.
+const OFFSET_BIAS: i32 = 1440;
+
+const fn mask(bits: u32) -> u64 {
+    (1u64 << bits) - 1
+}
+
+/// A date/time decoded into a single 64-bit word: year (14 bits), month (4),
+/// day (5), hour (5), minute (6), second (6), millisecond (10), and a signed
+/// UTC offset in minutes (12, biased), following packedtime-rs's layout.
+///
+/// Field access and the `truncate_to_*` operations are constant-time bit
+/// masks, with no decode/re-encode round trip through `tm`. Years are stored
+/// unsigned (0..=16383), so dates before year 0 are not representable.
+///
+/// `Ord`/`PartialOrd` are implemented explicitly over `to_timestamp()`
+/// (chronological order) rather than derived: the offset field occupies the
+/// highest bits of the packed word (see `OFFSET_SHIFT`), so a derived,
+/// bitwise ordering would sort by UTC offset before year/month/day/….
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct PackedTimestamp(u64);
+
+impl Ord for PackedTimestamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_timestamp().cmp(&other.to_timestamp())
+    }
+}
+
+impl PartialOrd for PackedTimestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PackedTimestamp {
+    /// Packs a Unix timestamp in seconds (UTC), a milliseconds component,
+    /// and a signed UTC offset in minutes into a `PackedTimestamp`. The
+    /// packed date/time fields are the local wall-clock at `offset_min`.
+    pub fn from_timestamp(secs: i64, millis: u32, offset_min: i32) -> Result<Self, Error> {
+        if millis > 999 || offset_min.unsigned_abs() > OFFSET_BIAS as u32 {
+            return Err(Error::FormatError);
+        }
+        let local_secs = secs
+            .checked_add(offset_min as i64 * 60)
+            .ok_or(Error::TimestampOverflowError)?;
+        let (year, month, day, hour, minute, second) = epoch_to_civil(local_secs);
+        if !(0..(1 << YEAR_BITS)).contains(&year) {
+            return Err(Error::TimestampOverflowError);
+        }
+
+        let offset_biased = (offset_min + OFFSET_BIAS) as u64;
+        let packed = (year as u64) << YEAR_SHIFT
+            | (month as u64) << MONTH_SHIFT
+            | (day as u64) << DAY_SHIFT
+            | (hour as u64) << HOUR_SHIFT
+            | (minute as u64) << MINUTE_SHIFT
+            | (second as u64) << SECOND_SHIFT
+            | (millis as u64) << MILLIS_SHIFT
+            | offset_biased << OFFSET_SHIFT;
+        Ok(Self(packed))
+    }
+
+    fn field(self, shift: u32, bits: u32) -> u64 {
+        (self.0 >> shift) & mask(bits)
+    }
+
+    pub fn year(self) -> i32 {
+        self.field(YEAR_SHIFT, YEAR_BITS) as i32
+    }
+
+    pub fn month(self) -> u32 {
+        self.field(MONTH_SHIFT, MONTH_BITS) as u32
+    }
+
+    pub fn day(self) -> u32 {
+        self.field(DAY_SHIFT, DAY_BITS) as u32
+    }
+
+    pub fn hour(self) -> u32 {
+        self.field(HOUR_SHIFT, HOUR_BITS) as u32
+    }
+
+    pub fn minute(self) -> u32 {
+        self.field(MINUTE_SHIFT, MINUTE_BITS) as u32
+    }
+
+    pub fn second(self) -> u32 {
+        self.field(SECOND_SHIFT, SECOND_BITS) as u32
+    }
+
+    pub fn millisecond(self) -> u32 {
+        self.field(MILLIS_SHIFT, MILLIS_BITS) as u32
+    }
+
+    pub fn offset_minutes(self) -> i32 {
+        self.field(OFFSET_SHIFT, OFFSET_BITS) as i32 - OFFSET_BIAS
+    }
+
+    /// Recovers the Unix timestamp in seconds (UTC); the millisecond
+    /// component is dropped.
+    pub fn to_timestamp(self) -> i64 {
+        let local_secs = civil_to_epoch(self.year() as i64, self.month(), self.day(), self.hour(), self.minute(), self.second());
+        local_secs - self.offset_minutes() as i64 * 60
+    }
+
+    /// Zeroes the hour/minute/second/millisecond fields: truncates to the
+    /// start of the packed local day.
+    pub fn truncate_to_day(self) -> Self {
+        Self(self.0 & !Self::fields_below(DAY_SHIFT))
+    }
+
+    /// Zeroes the minute/second/millisecond fields: truncates to the start
+    /// of the packed local hour.
+    pub fn truncate_to_hour(self) -> Self {
+        Self(self.0 & !Self::fields_below(HOUR_SHIFT))
+    }
+
+    /// Zeroes the second/millisecond fields: truncates to the start of the
+    /// packed local minute.
+    pub fn truncate_to_minute(self) -> Self {
+        Self(self.0 & !Self::fields_below(MINUTE_SHIFT))
+    }
+
+    /// All bits belonging to fields finer-grained than the one starting at
+    /// `shift` (i.e. bits `0..shift`).
+    fn fields_below(shift: u32) -> u64 {
+        mask(shift)
+    }
+
+    /// Parses `s` against `format` as UTC (like `parse_strftime_tz` with
+    /// `Tz::Utc`) and packs the result with a zero offset and no fractional
+    /// seconds.
+    pub fn parse(s: impl AsRef<str>, format: impl AsRef<str>) -> Result<Self, Error> {
+        let secs = parse_strftime_tz(s, Tz::Utc, format)?;
+        Self::from_timestamp(secs, 0, 0)
+    }
+
+    /// Formats the packed instant, applying its packed UTC offset, using
+    /// `format`.
+    pub fn format(self, format: impl AsRef<str>) -> Result<String, Error> {
+        strftime_format_tz(self.to_timestamp(), Tz::FixedOffset(self.offset_minutes() * 60), format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_timestamp_round_trip() {
+        let packed = PackedTimestamp::from_timestamp(1669113150, 250, 0).unwrap();
+        assert_eq!(packed.year(), 2022);
+        assert_eq!(packed.month(), 11);
+        assert_eq!(packed.day(), 22);
+        assert_eq!(packed.hour(), 10);
+        assert_eq!(packed.minute(), 32);
+        assert_eq!(packed.second(), 30);
+        assert_eq!(packed.millisecond(), 250);
+        assert_eq!(packed.offset_minutes(), 0);
+        assert_eq!(packed.to_timestamp(), 1669113150);
+    }
+
+    #[test]
+    fn test_from_timestamp_with_offset() {
+        let packed = PackedTimestamp::from_timestamp(0, 0, 60).unwrap();
+        assert_eq!((packed.year(), packed.month(), packed.day()), (1970, 1, 1));
+        assert_eq!((packed.hour(), packed.minute()), (1, 0));
+        assert_eq!(packed.offset_minutes(), 60);
+        assert_eq!(packed.to_timestamp(), 0);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let packed = PackedTimestamp::from_timestamp(1669113150, 250, 0).unwrap();
+        let day = packed.truncate_to_day();
+        assert_eq!((day.hour(), day.minute(), day.second(), day.millisecond()), (0, 0, 0, 0));
+        assert_eq!((day.year(), day.month(), day.day()), (2022, 11, 22));
+
+        let hour = packed.truncate_to_hour();
+        assert_eq!((hour.hour(), hour.minute(), hour.second(), hour.millisecond()), (10, 0, 0, 0));
+
+        let minute = packed.truncate_to_minute();
+        assert_eq!((minute.hour(), minute.minute(), minute.second(), minute.millisecond()), (10, 32, 0, 0));
+    }
+
+    #[test]
+    fn test_truncate_preserves_offset() {
+        let packed = PackedTimestamp::from_timestamp(1669113150, 250, 60).unwrap();
+        assert_eq!(packed.truncate_to_day().offset_minutes(), 60);
+        assert_eq!(packed.truncate_to_hour().offset_minutes(), 60);
+        assert_eq!(packed.truncate_to_minute().offset_minutes(), 60);
+    }
+
+    #[test]
+    fn test_ord_is_chronological_not_bitwise() {
+        let later = PackedTimestamp::from_timestamp(1669113150, 0, 0).unwrap(); // 2022-11-22
+        let earlier_with_offset = PackedTimestamp::from_timestamp(0, 0, 60).unwrap(); // 1970-01-01, +01:00
+        assert!(earlier_with_offset < later);
+        assert_eq!(earlier_with_offset.cmp(&later), std::cmp::Ordering::Less);
+
+        let mut sorted = vec![later, earlier_with_offset];
+        sorted.sort();
+        assert_eq!(sorted, vec![earlier_with_offset, later]);
+    }
+
+    #[test]
+    fn test_parse_and_format() {
+        let packed = PackedTimestamp::parse("2022-11-22 10:32:30", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(packed.format("%Y-%m-%d %H:%M:%S").unwrap(), "2022-11-22 10:32:30");
+    }
+}
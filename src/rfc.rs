@@ -0,0 +1,192 @@
+//! RFC 3339 / ISO 8601 and RFC 2822 convenience helpers.
+//!
+//! Formatting is built on the existing `strftime_format_tz` machinery, since
+//! assembling the right format string by hand is error-prone. Parsing does
+//! not hand off to `strptime`/the pure-Rust interpreter at all: both accept
+//! more than these standards actually allow, so the structural invariants
+//! (field widths, separators, the zone suffix) are validated directly here,
+//! and epoch seconds are computed straight from the validated fields via
+//! `civil_math`.
+
+use crate::civil_math::civil_to_epoch;
+use crate::{strftime_format_tz, Error, Tz};
+
+/// Formats `timestamp` (Unix seconds, UTC) as RFC 3339 / ISO 8601, e.g.
+/// `"1970-01-01T00:00:00Z"`.
+pub fn format_rfc3339(timestamp: i64) -> Result<String, Error> {
+    strftime_format_tz(timestamp, Tz::Utc, "%Y-%m-%dT%H:%M:%SZ")
+}
+
+/// Parses an RFC 3339 / ISO 8601 string into Unix seconds (UTC).
+///
+/// Requires exactly `YYYY-MM-DDTHH:MM:SS` followed by `Z` or a `+HH:MM` /
+/// `-HH:MM` offset; every other field must be present and zero-padded to
+/// its fixed width. `parse_rfc3339("1970-01-01T01:00:00+01:00")` is `0`.
+pub fn parse_rfc3339(s: impl AsRef<str>) -> Result<i64, Error> {
+    let s = s.as_ref();
+    let bytes = s.as_bytes();
+    if bytes.len() < 20 {
+        return Err(Error::DateTimeParseError);
+    }
+    if bytes[4] != b'-' || bytes[7] != b'-' || bytes[10] != b'T' || bytes[13] != b':' || bytes[16] != b':' {
+        return Err(Error::DateTimeParseError);
+    }
+    for &i in &[0, 1, 2, 3, 5, 6, 8, 9, 11, 12, 14, 15, 17, 18] {
+        if !bytes[i].is_ascii_digit() {
+            return Err(Error::DateTimeParseError);
+        }
+    }
+
+    let year = fold_digits(&bytes[0..4])? as i64;
+    let month = fold_digits(&bytes[5..7])?;
+    let day = fold_digits(&bytes[8..10])?;
+    let hour = fold_digits(&bytes[11..13])?;
+    let minute = fold_digits(&bytes[14..16])?;
+    let second = fold_digits(&bytes[17..19])?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return Err(Error::DateTimeParseError);
+    }
+
+    let offset_secs = parse_rfc3339_offset(&bytes[19..])?;
+    Ok(civil_to_epoch(year, month, day, hour, minute, second) - offset_secs as i64)
+}
+
+/// Parses the `Z` / `+HH:MM` / `-HH:MM` suffix of an RFC 3339 string into
+/// signed seconds east of UTC.
+fn parse_rfc3339_offset(bytes: &[u8]) -> Result<i32, Error> {
+    if bytes == b"Z" || bytes == b"z" {
+        return Ok(0);
+    }
+    if bytes.len() != 6 || bytes[3] != b':' {
+        return Err(Error::DateTimeParseError);
+    }
+    let sign: i32 = match bytes[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return Err(Error::DateTimeParseError),
+    };
+    let hh = fold_digits(&bytes[1..3])?;
+    let mm = fold_digits(&bytes[4..6])?;
+    if hh > 23 || mm > 59 {
+        return Err(Error::DateTimeParseError);
+    }
+    Ok(sign * (hh as i32 * 3600 + mm as i32 * 60))
+}
+
+/// Folds a run of ASCII-digit bytes into the integer it spells out.
+fn fold_digits(bytes: &[u8]) -> Result<u32, Error> {
+    bytes.iter().try_fold(0u32, |acc, &b| {
+        if !b.is_ascii_digit() {
+            return Err(Error::DateTimeParseError);
+        }
+        Ok(acc * 10 + (b - b'0') as u32)
+    })
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Formats `timestamp` (Unix seconds, UTC) as RFC 2822, e.g.
+/// `"Thu, 01 Jan 1970 00:00:00 +0000"`.
+pub fn format_rfc2822(timestamp: i64) -> Result<String, Error> {
+    strftime_format_tz(timestamp, Tz::Utc, "%a, %d %b %Y %H:%M:%S +0000")
+}
+
+/// Parses an RFC 2822 date time into Unix seconds.
+///
+/// Requires `[Www, ]DD Mon YYYY HH:MM:SS ZONE`, where `ZONE` is a numeric
+/// `+HHMM`/`-HHMM` offset or one of `UT`/`GMT`/`Z` (treated as `+0000`); the
+/// optional leading weekday name is not cross-checked against the date.
+pub fn parse_rfc2822(s: impl AsRef<str>) -> Result<i64, Error> {
+    let s = s.as_ref();
+    let s = match s.split_once(", ") {
+        Some((_weekday, rest)) => rest,
+        None => s,
+    };
+    let parts: Vec<&str> = s.split_whitespace().collect();
+    if parts.len() != 5 {
+        return Err(Error::DateTimeParseError);
+    }
+
+    let day: u32 = parts[0].parse().map_err(|_| Error::DateTimeParseError)?;
+    let month = MONTH_NAMES
+        .iter()
+        .position(|&m| m == parts[1])
+        .map(|i| i as u32 + 1)
+        .ok_or(Error::DateTimeParseError)?;
+    let year: i64 = parts[2].parse().map_err(|_| Error::DateTimeParseError)?;
+    if !(1..=31).contains(&day) {
+        return Err(Error::DateTimeParseError);
+    }
+
+    let hms: Vec<&str> = parts[3].split(':').collect();
+    if hms.len() != 3 {
+        return Err(Error::DateTimeParseError);
+    }
+    let hour: u32 = hms[0].parse().map_err(|_| Error::DateTimeParseError)?;
+    let minute: u32 = hms[1].parse().map_err(|_| Error::DateTimeParseError)?;
+    let second: u32 = hms[2].parse().map_err(|_| Error::DateTimeParseError)?;
+    if hour > 23 || minute > 59 || second > 60 {
+        return Err(Error::DateTimeParseError);
+    }
+
+    let offset_secs = parse_rfc2822_zone(parts[4])?;
+    Ok(civil_to_epoch(year, month, day, hour, minute, second) - offset_secs as i64)
+}
+
+/// Parses an RFC 2822 zone: `+HHMM`/`-HHMM`, or `UT`/`GMT`/`Z` as `+0000`.
+fn parse_rfc2822_zone(zone: &str) -> Result<i32, Error> {
+    match zone {
+        "UT" | "GMT" | "Z" => return Ok(0),
+        _ => {}
+    }
+    let bytes = zone.as_bytes();
+    if bytes.len() != 5 {
+        return Err(Error::DateTimeParseError);
+    }
+    let sign: i32 = match bytes[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return Err(Error::DateTimeParseError),
+    };
+    let hh = fold_digits(&bytes[1..3])?;
+    let mm = fold_digits(&bytes[3..5])?;
+    if hh > 23 || mm > 59 {
+        return Err(Error::DateTimeParseError);
+    }
+    Ok(sign * (hh as i32 * 3600 + mm as i32 * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_rfc3339() {
+        assert_eq!(format_rfc3339(0).unwrap(), "1970-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_parse_rfc3339_round_trip() {
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00Z").unwrap(), 0);
+        assert_eq!(parse_rfc3339("1970-01-01T01:00:00+01:00").unwrap(), 0);
+        assert_eq!(parse_rfc3339("1969-12-31T23:00:00-01:00").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_parse_rfc3339_rejects_malformed_input() {
+        assert!(parse_rfc3339("1970-01-01 00:00:00Z").is_err()); // space instead of T
+        assert!(parse_rfc3339("1970-01-01T00:00:00").is_err()); // missing zone
+        assert!(parse_rfc3339("1970-1-01T00:00:00Z").is_err()); // not zero-padded
+        assert!(parse_rfc3339("1970-01-01T00:00:00+0100").is_err()); // missing ':' in offset
+    }
+
+    #[test]
+    fn test_format_and_parse_rfc2822_round_trip() {
+        let date_time = format_rfc2822(0).unwrap();
+        assert_eq!(date_time, "Thu, 01 Jan 1970 00:00:00 +0000");
+        assert_eq!(parse_rfc2822(&date_time).unwrap(), 0);
+        assert_eq!(parse_rfc2822("01 Jan 1970 01:00:00 +0100").unwrap(), 0);
+    }
+}